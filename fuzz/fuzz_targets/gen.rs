@@ -0,0 +1,222 @@
+//! Generates random-but-valid `HLTAS` scripts and `Operation`s that target real state read back
+//! from them, so the fuzzer exercises real apply/undo state transitions instead of immediately
+//! bailing out on an out-of-range index.
+
+use arbitrary::{Result, Unstructured};
+use bxt_rs::modules::tas_studio::editor::operation::{Key, Operation};
+use hltas::HLTAS;
+
+const MOVEMENT_LETTERS: [u8; 6] = *b"flrbud";
+const ACTION_LETTERS: [u8; 6] = *b"jdu12r";
+const KEYS: [Key; 12] = [
+    Key::Forward,
+    Key::Left,
+    Key::Right,
+    Key::Back,
+    Key::Up,
+    Key::Down,
+    Key::Jump,
+    Key::Duck,
+    Key::Use,
+    Key::Attack1,
+    Key::Attack2,
+    Key::Reload,
+];
+
+/// A single generated frame bulk, kept around so we can build ops against the values it was
+/// generated with without re-parsing the HLTAS text back out.
+struct Bulk {
+    /// `true` if this bulk uses the left-right-count encoding (`s06-------`) instead of a plain
+    /// yaw (`----------`).
+    is_left_right: bool,
+    /// Either the yaw or the left-right count, depending on `is_left_right`.
+    value: u32,
+    frame_count: u32,
+    movement: [bool; 6],
+    action: [bool; 6],
+}
+
+impl Bulk {
+    fn arbitrary(u: &mut Unstructured) -> Result<Self> {
+        Ok(Self {
+            is_left_right: u.arbitrary()?,
+            value: u.int_in_range(1..=20)?,
+            frame_count: u.int_in_range(1..=20)?,
+            movement: u.arbitrary()?,
+            action: u.arbitrary()?,
+        })
+    }
+
+    fn to_line(&self) -> String {
+        let mut col1 = [b'-'; 10];
+        if self.is_left_right {
+            col1[0] = b's';
+            col1[1] = b'0';
+            col1[2] = b'6';
+        }
+
+        let mut movement = [b'-'; 6];
+        for (i, set) in self.movement.iter().enumerate() {
+            if *set {
+                movement[i] = MOVEMENT_LETTERS[i];
+            }
+        }
+
+        let mut action = [b'-'; 6];
+        for (i, set) in self.action.iter().enumerate() {
+            if *set {
+                action[i] = ACTION_LETTERS[i];
+            }
+        }
+
+        format!(
+            "{}|{}|{}|0.004|{}|-|{}",
+            std::str::from_utf8(&col1).unwrap(),
+            std::str::from_utf8(&movement).unwrap(),
+            std::str::from_utf8(&action).unwrap(),
+            self.value,
+            self.frame_count,
+        )
+    }
+}
+
+/// Builds a random valid `HLTAS` made up of 1 to 8 frame bulks, together with the exact line text
+/// each one was built from (the `hltas` crate exposes no way to serialize a `Line` back to text,
+/// so `Insert`/`Delete`/`Replace` need this to build a line/from/to that will actually parse back
+/// to the same state).
+pub fn arbitrary_hltas(u: &mut Unstructured) -> Result<(HLTAS, Vec<String>)> {
+    let bulk_count: usize = u.int_in_range(1..=8)?;
+
+    let mut text = "version 1\nframes\n".to_string();
+    let mut lines = Vec::with_capacity(bulk_count);
+    for i in 0..bulk_count {
+        let bulk = Bulk::arbitrary(u)?;
+        if i > 0 {
+            text.push('\n');
+        }
+        let line = bulk.to_line();
+        text.push_str(&line);
+        lines.push(line);
+    }
+
+    let hltas = HLTAS::from_str(&text).map_err(|_| arbitrary::Error::IncorrectFormat)?;
+    Ok((hltas, lines))
+}
+
+/// Builds a random `Operation` whose indices and `from` values are valid for `hltas` as
+/// generated by [`arbitrary_hltas`] (one frame bulk per line, in order). `lines` must be the line
+/// text returned alongside `hltas` by that same call.
+pub fn arbitrary_operation(
+    u: &mut Unstructured,
+    hltas: &HLTAS,
+    lines: &[String],
+) -> Result<Operation> {
+    let line_count = hltas.lines.len();
+    if line_count == 0 {
+        return Err(arbitrary::Error::IncorrectFormat);
+    }
+
+    // Keep `Sequence` shallow and rare so most fuzzing time goes to the primitives it wraps.
+    if line_count >= 2 && u.ratio(1, 16)? {
+        let a = arbitrary_primitive_op(u, hltas, lines)?;
+        let b = arbitrary_primitive_op(u, hltas, lines)?;
+        return Ok(Operation::Sequence(vec![a, b]));
+    }
+
+    arbitrary_primitive_op(u, hltas, lines)
+}
+
+fn arbitrary_primitive_op(
+    u: &mut Unstructured,
+    hltas: &HLTAS,
+    lines: &[String],
+) -> Result<Operation> {
+    let line_count = hltas.lines.len();
+    let line_idx = u.int_in_range(0..=line_count - 1)?;
+
+    // Read the live field values back out through a scratch clone, using the same `_mut`
+    // accessors `Operation::apply` itself uses, rather than assuming separate read-only getters.
+    let mut probe = hltas.clone();
+    let bulk = probe.lines[line_idx]
+        .frame_bulk_mut()
+        .expect("generator only produces frame bulk lines");
+    let frame_count = bulk.frame_count.get();
+    let yaw = bulk.yaw_mut().map(|yaw| *yaw);
+    let left_right_count = bulk.left_right_count_mut().map(|count| count.get());
+
+    // Bias towards operations on the bulk's own fields; only reach for Split/MoveLine/Delete when
+    // there is room to split into, a second line to move against, or (for Delete) at least one
+    // line left once it's removed. Insert and Replace are always generated when chosen.
+    match u.int_in_range(0..=8)? {
+        0 => Ok(Operation::SetFrameCount {
+            bulk_idx: line_idx,
+            from: frame_count,
+            to: u.int_in_range(1..=20)?,
+        }),
+        1 if yaw.is_some() => Ok(Operation::SetYaw {
+            bulk_idx: line_idx,
+            from: yaw.unwrap(),
+            to: u.int_in_range(0..=359)? as f32,
+        }),
+        2 if left_right_count.is_some() => Ok(Operation::SetLeftRightCount {
+            bulk_idx: line_idx,
+            from: left_right_count.unwrap(),
+            to: u.int_in_range(1..=20)?,
+        }),
+        3 => {
+            let key = KEYS[u.int_in_range(0..=KEYS.len() - 1)?];
+            let current = *key.value_mut(bulk);
+            Ok(Operation::ToggleKey {
+                bulk_idx: line_idx,
+                key,
+                to: !current,
+            })
+        }
+        4 if frame_count > 1 => {
+            let offset: u32 = probe.lines[..line_idx]
+                .iter()
+                .map(|line| {
+                    line.frame_bulk()
+                        .map(|bulk| bulk.frame_count.get())
+                        .unwrap_or(0)
+                })
+                .sum();
+            let local = u.int_in_range(1..=frame_count - 1)?;
+            Ok(Operation::Split {
+                frame_idx: (offset + local) as usize,
+            })
+        }
+        5 if line_count >= 2 => {
+            let to_idx = u.int_in_range(0..=line_count - 1)?;
+            Ok(Operation::MoveLine {
+                from_idx: line_idx,
+                to_idx,
+            })
+        }
+        6 => {
+            // `line_idx` may be up to (and including) `line_count`, i.e. inserting after the last
+            // line, unlike every other op here which targets an existing line.
+            let insert_idx = u.int_in_range(0..=line_count)?;
+            Ok(Operation::Insert {
+                line_idx: insert_idx,
+                line: Bulk::arbitrary(u)?.to_line(),
+            })
+        }
+        7 if line_count >= 2 => Ok(Operation::Delete {
+            line_idx,
+            line: lines[line_idx].clone(),
+        }),
+        8 => Ok(Operation::Replace {
+            line_idx,
+            from: lines[line_idx].clone(),
+            to: Bulk::arbitrary(u)?.to_line(),
+        }),
+        // The chosen arm's guard did not hold for this bulk/line count; fall back to the one op
+        // that is always valid.
+        _ => Ok(Operation::SetFrameCount {
+            bulk_idx: line_idx,
+            from: frame_count,
+            to: u.int_in_range(1..=20)?,
+        }),
+    }
+}
@@ -0,0 +1,69 @@
+//! Randomized apply<->undo roundtrip fuzzing for `Operation`.
+//!
+//! Builds a random, valid `HLTAS` plus a random `Operation` that targets real, in-range state
+//! taken from that script, then checks the invariant that `apply()` followed by `undo()`
+//! reproduces the original script byte-for-byte, and that the initial frame is never
+//! invalidated.
+
+#![no_main]
+
+use bxt_rs::modules::tas_studio::editor::operation::Operation;
+use hltas::HLTAS;
+use libfuzzer_sys::fuzz_target;
+
+mod gen;
+
+use gen::arbitrary_hltas;
+
+fuzz_target!(|data: &[u8]| {
+    let mut u = arbitrary::Unstructured::new(data);
+
+    let Ok((hltas, lines)) = arbitrary_hltas(&mut u) else {
+        return;
+    };
+
+    let Ok(op) = gen::arbitrary_operation(&mut u, &hltas, &lines) else {
+        return;
+    };
+
+    check_roundtrip(&hltas, &op);
+});
+
+fn check_roundtrip(hltas: &HLTAS, op: &Operation) {
+    let mut modified = hltas.clone();
+
+    let first_affected = match op.apply(&mut modified) {
+        Ok(first_affected) => first_affected,
+        // Only `Sequence` can legitimately fail: one of its own inner ops can go stale relative to
+        // an earlier inner op in the same sequence, and that's exercised (and rolled back) here
+        // rather than treated as an error. Any other variant failing means the generator produced
+        // an op that wasn't actually in-range for `hltas`, which is a generator bug, not something
+        // to swallow silently.
+        Err(err) => {
+            assert!(
+                matches!(op, Operation::Sequence(_)),
+                "apply() failed for a non-Sequence op {op:?}: {err}"
+            );
+            return;
+        }
+    };
+    assert_ne!(
+        first_affected,
+        Some(0),
+        "apply() invalidated the initial frame for {op:?}"
+    );
+
+    let first_affected = op
+        .undo(&mut modified)
+        .unwrap_or_else(|err| panic!("undo() failed after a successful apply() for {op:?}: {err}"));
+    assert_ne!(
+        first_affected,
+        Some(0),
+        "undo() invalidated the initial frame for {op:?}"
+    );
+
+    assert_eq!(
+        &modified, hltas,
+        "apply() -> undo() did not reproduce the original HLTAS for {op:?}"
+    );
+}
@@ -1,6 +1,9 @@
 //! `hw`, `sw`, `hl`.
 
+use std::cell::Cell;
 use std::os::raw::*;
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use crate::{
     ffi,
@@ -8,11 +11,34 @@ use crate::{
     utils::{abort_on_panic, dl, Function, MainThreadMarker, Variable},
 };
 
+mod sig_scan;
+
+use sig_scan::Signature;
+
+// Array-of-bytes fallbacks for each target, used when `dlsym()` can't find the symbol (stripped
+// `hw.so` builds, `hw.dll`). Empty until we have signatures verified against an actual build; an
+// empty list just means `find_pointer` falls back to `None`, same as today. This means the
+// scanning fallback does not yet recover any pointer on a stripped build - it is scaffolding
+// (`sig_scan` itself is exercised by its own unit tests) until real signatures are filled in here.
+const CMD_ADDMALLOCCOMMAND_SIG: &[Signature] = &[];
+const CMD_FUNCTIONS_SIG: &[Signature] = &[];
+const CON_PRINTF_SIG: &[Signature] = &[];
+const CVAR_DIRECTSET_SIG: &[Signature] = &[];
+const CVAR_REGISTERVARIABLE_SIG: &[Signature] = &[];
+const CVAR_VARS_SIG: &[Signature] = &[];
+const HOST_SHUTDOWN_SIG: &[Signature] = &[];
+const MEMORY_INIT_SIG: &[Signature] = &[];
+const MEM_FREE_SIG: &[Signature] = &[];
+const V_FADEALPHA_SIG: &[Signature] = &[];
+const Z_FREE_SIG: &[Signature] = &[];
+
 pub static CMD_ADDMALLOCCOMMAND: Function<
     unsafe extern "C" fn(*const c_char, unsafe extern "C" fn(), c_int),
 > = Function::empty();
 pub static CMD_FUNCTIONS: Variable<*mut ffi::command::cmd_function_s> = Variable::empty();
 pub static CON_PRINTF: Function<unsafe extern "C" fn(*const c_char, ...)> = Function::empty();
+pub static CVAR_DIRECTSET: Function<unsafe extern "C" fn(*mut ffi::cvar::cvar_s, *const c_char)> =
+    Function::empty();
 pub static CVAR_REGISTERVARIABLE: Function<unsafe extern "C" fn(*mut ffi::cvar::cvar_s)> =
     Function::empty();
 pub static CVAR_VARS: Variable<*mut ffi::cvar::cvar_s> = Variable::empty();
@@ -23,20 +49,69 @@ pub static MEM_FREE: Function<unsafe extern "C" fn(*mut c_void)> = Function::emp
 pub static V_FADEALPHA: Function<unsafe extern "C" fn() -> c_int> = Function::empty();
 pub static Z_FREE: Function<unsafe extern "C" fn(*mut c_void)> = Function::empty();
 
+thread_local! {
+    /// The name of the module currently executing inside a recoverable hook (see
+    /// `catch_module_panic`), so the panic hook installed by `install_panic_hook` can report which
+    /// module was responsible.
+    static CURRENT_MODULE: Cell<Option<&'static str>> = Cell::new(None);
+}
+
+/// Set by `disable_faulting_module` once `fade_remove`'s hook has panicked, so every subsequent
+/// `V_FadeAlpha` call skips `fade_remove::is_active` instead of panicking identically again.
+static FADE_REMOVE_DISABLED: AtomicBool = AtomicBool::new(false);
+
+/// Returns `true` if `module` was disabled by a previous call to `disable_faulting_module`.
+fn is_module_disabled(module: &str) -> bool {
+    match module {
+        "fade_remove" => FADE_REMOVE_DISABLED.load(Ordering::Relaxed),
+        _ => false,
+    }
+}
+
+/// Resolves a single pointer: symbol lookup first, with signature scanning as a fallback for
+/// builds where the symbol isn't exported.
+unsafe fn find_pointer<T>(handle: &dl::Handle, symbol: &str, signatures: &[Signature]) -> Option<T> {
+    if let Ok(ptr) = handle.sym(symbol) {
+        return Some(ptr);
+    }
+
+    if signatures.is_empty() {
+        return None;
+    }
+
+    match sig_scan::find(handle, signatures) {
+        Ok(address) => Some(std::mem::transmute_copy(&address)),
+        Err(err) => {
+            warn!("signature scan for {symbol} failed: {err:?}");
+            None
+        }
+    }
+}
+
 fn find_pointers(marker: MainThreadMarker) {
     let handle = dl::open("hw.so").unwrap();
 
     unsafe {
-        CMD_ADDMALLOCCOMMAND.set(marker, handle.sym("Cmd_AddMallocCommand").ok());
-        CMD_FUNCTIONS.set(marker, handle.sym("cmd_functions").ok());
-        CON_PRINTF.set(marker, handle.sym("Con_Printf").ok());
-        CVAR_REGISTERVARIABLE.set(marker, handle.sym("Cvar_RegisterVariable").ok());
-        CVAR_VARS.set(marker, handle.sym("cvar_vars").ok());
-        HOST_SHUTDOWN.set(marker, handle.sym("Host_Shutdown").ok());
-        MEMORY_INIT.set(marker, handle.sym("Memory_Init").ok());
-        MEM_FREE.set(marker, handle.sym("Mem_Free").ok());
-        V_FADEALPHA.set(marker, handle.sym("V_FadeAlpha").ok());
-        Z_FREE.set(marker, handle.sym("Z_Free").ok());
+        CMD_ADDMALLOCCOMMAND.set(
+            marker,
+            find_pointer(&handle, "Cmd_AddMallocCommand", CMD_ADDMALLOCCOMMAND_SIG),
+        );
+        CMD_FUNCTIONS.set(marker, find_pointer(&handle, "cmd_functions", CMD_FUNCTIONS_SIG));
+        CON_PRINTF.set(marker, find_pointer(&handle, "Con_Printf", CON_PRINTF_SIG));
+        CVAR_DIRECTSET.set(
+            marker,
+            find_pointer(&handle, "Cvar_DirectSet", CVAR_DIRECTSET_SIG),
+        );
+        CVAR_REGISTERVARIABLE.set(
+            marker,
+            find_pointer(&handle, "Cvar_RegisterVariable", CVAR_REGISTERVARIABLE_SIG),
+        );
+        CVAR_VARS.set(marker, find_pointer(&handle, "cvar_vars", CVAR_VARS_SIG));
+        HOST_SHUTDOWN.set(marker, find_pointer(&handle, "Host_Shutdown", HOST_SHUTDOWN_SIG));
+        MEMORY_INIT.set(marker, find_pointer(&handle, "Memory_Init", MEMORY_INIT_SIG));
+        MEM_FREE.set(marker, find_pointer(&handle, "Mem_Free", MEM_FREE_SIG));
+        V_FADEALPHA.set(marker, find_pointer(&handle, "V_FadeAlpha", V_FADEALPHA_SIG));
+        Z_FREE.set(marker, find_pointer(&handle, "Z_Free", Z_FREE_SIG));
     }
 }
 
@@ -44,6 +119,7 @@ fn reset_pointers(marker: MainThreadMarker) {
     CMD_ADDMALLOCCOMMAND.reset(marker);
     CMD_FUNCTIONS.reset(marker);
     CON_PRINTF.reset(marker);
+    CVAR_DIRECTSET.reset(marker);
     CVAR_REGISTERVARIABLE.reset(marker);
     CVAR_VARS.reset(marker);
     HOST_SHUTDOWN.reset(marker);
@@ -53,12 +129,64 @@ fn reset_pointers(marker: MainThreadMarker) {
     Z_FREE.reset(marker);
 }
 
+/// Installs a panic hook that, in addition to the existing log output, prints the panic's
+/// message and location through the game console (if resolved at the time of the panic), so
+/// players launching the game normally get an on-screen diagnostic rather than a bare crash.
+/// `abort_on_panic` still aborts afterwards; this only changes where the message is visible.
+///
+/// Idempotent: `Memory_Init` (the only caller) can run more than once per process (for example on
+/// a level change), and installing the hook again would nest a second wrapper around the first,
+/// duplicating every subsequent panic message.
+fn install_panic_hook() {
+    static INSTALLED: std::sync::Once = std::sync::Once::new();
+
+    INSTALLED.call_once(install_panic_hook_once);
+}
+
+fn install_panic_hook_once() {
+    let default_hook = std::panic::take_hook();
+
+    std::panic::set_hook(Box::new(move |info| {
+        default_hook(info);
+
+        let marker = MainThreadMarker::new();
+        if !CON_PRINTF.is_set(marker) {
+            return;
+        }
+
+        let message = info
+            .payload()
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| info.payload().downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("Box<dyn Any>");
+
+        let location = info
+            .location()
+            .map(|location| location.to_string())
+            .unwrap_or_else(|| "unknown location".to_string());
+
+        let module = CURRENT_MODULE.with(|current| current.get());
+        let formatted = match module {
+            Some(module) => format!("bxt-rs panicked in module {module} at {location}:\n{message}\n\0"),
+            None => format!("bxt-rs panicked at {location}:\n{message}\n\0"),
+        };
+
+        // Safety: `CON_PRINTF` was just confirmed to be resolved, and we pass the message through
+        // a fixed "%s" format string so it can't be misinterpreted as one.
+        unsafe {
+            CON_PRINTF.get(marker)(b"%s\0".as_ptr().cast(), formatted.as_ptr());
+        }
+    }));
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn Memory_Init(buf: *mut c_void, size: c_int) -> c_int {
     abort_on_panic(move || {
         let marker = MainThreadMarker::new();
 
         let _ = pretty_env_logger::try_init();
+        install_panic_hook();
 
         find_pointers(marker);
 
@@ -88,15 +216,96 @@ pub unsafe extern "C" fn Host_Shutdown() {
     })
 }
 
+/// Runs `f` as the body of an optional module's hook, recovering from a panic by disabling that
+/// module instead of letting `abort_on_panic` tear down the whole engine.
+///
+/// On `Err`, prints a warning through the game console (if resolved) and disables the faulting
+/// module (see [`disable_faulting_module`]), then calls `recover` to produce the hook's safe
+/// return value (for wrapper hooks like `V_FadeAlpha`, that's normally "fall through to the
+/// original engine function"). A second panic while recovering is treated as unrecoverable and
+/// aborts, since it means engine state may already be inconsistent.
+///
+/// Hooks where continuing after a panic could itself corrupt engine state (`Memory_Init`,
+/// `Host_Shutdown`) are not wrapped with this and keep hard-aborting via plain `abort_on_panic`.
+fn catch_module_panic<T>(
+    marker: MainThreadMarker,
+    module: &'static str,
+    f: impl FnOnce() -> T,
+    recover: impl FnOnce(MainThreadMarker) -> T,
+) -> T {
+    CURRENT_MODULE.with(|current| current.set(Some(module)));
+    let result = std::panic::catch_unwind(AssertUnwindSafe(f));
+    CURRENT_MODULE.with(|current| current.set(None));
+
+    let payload = match result {
+        Ok(value) => return value,
+        Err(payload) => payload,
+    };
+
+    let cleanup = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        disable_faulting_module(marker, module, &payload)
+    }));
+    if cleanup.is_err() {
+        // Cleanup itself panicked: engine state may be inconsistent, so don't risk continuing.
+        std::process::abort();
+    }
+
+    recover(marker)
+}
+
+/// Prints a warning and disables `module` after it panicked inside a [`catch_module_panic`]-
+/// wrapped hook, so its hook stops re-entering the code that panicked on every subsequent call.
+///
+/// Disabling is done by setting that module's dedicated flag (e.g. `FADE_REMOVE_DISABLED`),
+/// checked directly by the hook itself (see `V_FadeAlpha` below) - not by a generic
+/// `Module::is_enabled()` sweep. `fade_remove` has no `Module` registration in this tree for such
+/// a sweep to act on, so going through one here would silently do nothing, same as calling
+/// `cvars`/`commands` deregistration for a module neither subsystem knows about.
+fn disable_faulting_module(
+    marker: MainThreadMarker,
+    module: &'static str,
+    payload: &Box<dyn std::any::Any + Send>,
+) {
+    let message = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("Box<dyn Any>");
+
+    if CON_PRINTF.is_set(marker) {
+        let formatted = format!("bxt-rs: module {module} panicked and was disabled: {message}\n\0");
+
+        // Safety: `CON_PRINTF` was just confirmed to be resolved, and we pass the message
+        // through a fixed "%s" format string so it can't be misinterpreted as one.
+        unsafe {
+            CON_PRINTF.get(marker)(b"%s\0".as_ptr().cast(), formatted.as_ptr());
+        }
+    }
+
+    match module {
+        "fade_remove" => FADE_REMOVE_DISABLED.store(true, Ordering::Relaxed),
+        _ => {}
+    }
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn V_FadeAlpha() -> c_int {
     abort_on_panic(move || {
         let marker = MainThreadMarker::new();
 
-        if fade_remove::is_active(marker) {
-            0
-        } else {
-            V_FADEALPHA.get(marker)()
-        }
+        catch_module_panic(
+            marker,
+            "fade_remove",
+            || {
+                if is_module_disabled("fade_remove") {
+                    V_FADEALPHA.get(marker)()
+                } else if fade_remove::is_active(marker) {
+                    0
+                } else {
+                    V_FADEALPHA.get(marker)()
+                }
+            },
+            |marker| V_FADEALPHA.get(marker)(),
+        )
     })
 }
\ No newline at end of file
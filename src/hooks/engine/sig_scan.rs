@@ -0,0 +1,280 @@
+//! Array-of-bytes signature scanning.
+//!
+//! Used as a fallback for resolving engine pointers whose symbols are not exported, such as in
+//! stripped `hw.so` builds, where `dlsym()` fails. Only ELF images are parsed; PE images such as
+//! `hw.dll` on Windows are not handled.
+
+use std::ffi::c_void;
+
+use crate::utils::dl::Handle;
+
+/// One byte of a [`Signature`]: a fixed value to match, or `None` for a wildcard that matches
+/// anything.
+pub type SignatureByte = Option<u8>;
+
+/// A byte pattern to scan for, with `None` entries acting as wildcards.
+pub type Signature = &'static [SignatureByte];
+
+#[derive(Debug)]
+pub enum Error {
+    /// The module backing the handle could not be found in the process' memory map, or its image
+    /// is neither a 32-bit nor a 64-bit ELF image.
+    ModuleNotFound,
+    /// None of the provided signatures matched anywhere in the module.
+    NoMatch,
+    /// More than one signature matched, or the same signature matched more than once, so
+    /// returning a single address would be a guess rather than a certainty.
+    Ambiguous,
+}
+
+/// One executable, readable region of a loaded module.
+struct Segment {
+    start: *const u8,
+    len: usize,
+}
+
+/// Byte offset of `e_ident[EI_CLASS]` in an ELF header, identical in the 32- and 64-bit layouts.
+const EI_CLASS: usize = 4;
+
+/// Returns the bounds of every executable, readable `PT_LOAD` segment of the module backing
+/// `handle`.
+///
+/// Parses the ELF program headers at the module's load address, rather than trusting
+/// `/proc/self/maps` line contents, so a match can never be returned from memory that isn't
+/// actually executable.
+///
+/// `hw.so`/`hw.dll` (the GoldSrc/Half-Life engine these signatures target) have only ever shipped
+/// 32-bit, but this also handles 64-bit images so it isn't silently wrong if ever pointed at one:
+/// `Elf32_Phdr` and `Elf64_Phdr` are not layout-compatible (e.g. `p_flags` sits at a different
+/// offset), so which one to read is picked from `e_ident[EI_CLASS]` rather than assumed.
+fn executable_segments(handle: &Handle) -> Result<Vec<Segment>, Error> {
+    let base = handle.base_address().ok_or(Error::ModuleNotFound)?;
+
+    // Safety: `base` is the load address of a mapped ELF image (guaranteed by the dynamic
+    // linker), so the ELF identification bytes and headers at the start of it are valid to read.
+    unsafe {
+        match *base.add(EI_CLASS) {
+            libc::ELFCLASS32 => executable_segments_32(base),
+            libc::ELFCLASS64 => executable_segments_64(base),
+            class => {
+                warn!("unexpected ELF class {class}");
+                Err(Error::ModuleNotFound)
+            }
+        }
+    }
+}
+
+/// # Safety
+///
+/// `base` must be the load address of a mapped 32-bit ELF image.
+unsafe fn executable_segments_32(base: *const u8) -> Result<Vec<Segment>, Error> {
+    let ehdr = base as *const libc::Elf32_Ehdr;
+    let phdrs = base.add((*ehdr).e_phoff as usize) as *const libc::Elf32_Phdr;
+
+    let mut segments = Vec::new();
+    for i in 0..(*ehdr).e_phnum as usize {
+        let phdr = &*phdrs.add(i);
+
+        if phdr.p_type != libc::PT_LOAD
+            || phdr.p_flags & libc::PF_X == 0
+            || phdr.p_flags & libc::PF_R == 0
+        {
+            continue;
+        }
+
+        segments.push(Segment {
+            start: base.add(phdr.p_vaddr as usize),
+            len: phdr.p_memsz as usize,
+        });
+    }
+
+    Ok(segments)
+}
+
+/// # Safety
+///
+/// `base` must be the load address of a mapped 64-bit ELF image.
+unsafe fn executable_segments_64(base: *const u8) -> Result<Vec<Segment>, Error> {
+    let ehdr = base as *const libc::Elf64_Ehdr;
+    let phdrs = base.add((*ehdr).e_phoff as usize) as *const libc::Elf64_Phdr;
+
+    let mut segments = Vec::new();
+    for i in 0..(*ehdr).e_phnum as usize {
+        let phdr = &*phdrs.add(i);
+
+        if phdr.p_type != libc::PT_LOAD
+            || phdr.p_flags & libc::PF_X == 0
+            || phdr.p_flags & libc::PF_R == 0
+        {
+            continue;
+        }
+
+        segments.push(Segment {
+            start: base.add(phdr.p_vaddr as usize),
+            len: phdr.p_memsz as usize,
+        });
+    }
+
+    Ok(segments)
+}
+
+/// Builds a Boyer-Moore-Horspool bad-character skip table over `signature`, keyed on the last
+/// occurrence of each fixed byte. A wildcard matches any byte, so every skip is capped at the
+/// distance of the last wildcard before the final position from the end.
+fn build_skip_table(signature: Signature) -> [usize; 256] {
+    let last = signature.len() - 1;
+    let max_skip = signature[..last]
+        .iter()
+        .rposition(Option::is_none)
+        .map_or(signature.len(), |i| last - i);
+    let mut table = [max_skip; 256];
+
+    for (i, byte) in signature[..last].iter().enumerate() {
+        if let Some(byte) = byte {
+            table[*byte as usize] = (last - i).min(max_skip);
+        }
+    }
+
+    table
+}
+
+fn matches_at(window: &[u8], signature: Signature) -> bool {
+    window.len() == signature.len()
+        && window
+            .iter()
+            .zip(signature)
+            .all(|(byte, pattern)| pattern.map_or(true, |pattern| *byte == pattern))
+}
+
+/// Returns every address in `segment` where `signature` matches, using a Boyer-Moore-Horspool
+/// skip table built from its non-wildcard bytes.
+fn scan_segment(segment: &Segment, signature: Signature) -> Vec<*const u8> {
+    if signature.is_empty() || segment.len < signature.len() {
+        return Vec::new();
+    }
+
+    // Safety: `segment` was produced by `executable_segments`, which only returns the bounds of
+    // segments mapped by the dynamic linker for this module.
+    let haystack = unsafe { std::slice::from_raw_parts(segment.start, segment.len) };
+    let skip = build_skip_table(signature);
+
+    let mut matches = Vec::new();
+    let mut i = 0;
+    while i + signature.len() <= haystack.len() {
+        let window = &haystack[i..i + signature.len()];
+
+        if matches_at(window, signature) {
+            // Safety: `i` is within `segment`'s mapped range, checked by the loop condition above.
+            matches.push(unsafe { segment.start.add(i) });
+        }
+
+        i += skip[window[window.len() - 1] as usize];
+    }
+
+    matches
+}
+
+/// Scans every executable segment of the module backing `handle` for `signatures`, requiring
+/// exactly one match across the whole module so a bad or stale signature can never silently bind
+/// to the wrong address.
+pub fn find(handle: &Handle, signatures: &[Signature]) -> Result<*const c_void, Error> {
+    let segments = executable_segments(handle)?;
+
+    let mut found = None;
+    for signature in signatures {
+        for segment in &segments {
+            for address in scan_segment(segment, signature) {
+                if found.is_some() {
+                    return Err(Error::Ambiguous);
+                }
+
+                found = Some(address);
+            }
+        }
+    }
+
+    found.map(|address| address.cast()).ok_or(Error::NoMatch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skip_table_is_capped_by_a_wildcard_before_the_last_byte() {
+        // "A ? B": the wildcard is 1 from the end and can stand for any byte, so no skip may
+        // exceed 1, neither for the fixed bytes nor for bytes that don't appear.
+        let signature: Signature = &[Some(b'A'), None, Some(b'B')];
+        let table = build_skip_table(signature);
+
+        assert_eq!(table[b'A' as usize], 1);
+        assert_eq!(table[b'B' as usize], 1);
+        assert_eq!(table[b'C' as usize], 1);
+    }
+
+    #[test]
+    fn skip_table_ignores_wildcard_bytes() {
+        let signature: Signature = &[None, Some(b'A'), None];
+        let table = build_skip_table(signature);
+
+        // The only fixed byte is not the last one, so it skips by its distance from the end.
+        assert_eq!(table[b'A' as usize], 1);
+    }
+
+    #[test]
+    fn matches_at_respects_wildcards() {
+        let signature: Signature = &[Some(b'A'), None, Some(b'C')];
+
+        assert!(matches_at(b"ABC", signature));
+        assert!(matches_at(b"AXC", signature));
+        assert!(!matches_at(b"ABD", signature));
+    }
+
+    #[test]
+    fn matches_at_rejects_a_short_window() {
+        let signature: Signature = &[Some(b'A'), Some(b'B'), Some(b'C')];
+
+        assert!(!matches_at(b"AB", signature));
+    }
+
+    #[test]
+    fn scan_segment_finds_every_match() {
+        let haystack = b"XXABCXXABCXX".to_vec();
+        let segment = Segment {
+            start: haystack.as_ptr(),
+            len: haystack.len(),
+        };
+        let signature: Signature = &[Some(b'A'), None, Some(b'C')];
+
+        let matches = scan_segment(&segment, signature);
+
+        let offsets: Vec<usize> = matches
+            .iter()
+            .map(|&address| unsafe { address.offset_from(segment.start) as usize })
+            .collect();
+        assert_eq!(offsets, vec![2, 7]);
+    }
+
+    #[test]
+    fn scan_segment_finds_nothing_in_a_segment_shorter_than_the_signature() {
+        let haystack = b"AB".to_vec();
+        let segment = Segment {
+            start: haystack.as_ptr(),
+            len: haystack.len(),
+        };
+        let signature: Signature = &[Some(b'A'), Some(b'B'), Some(b'C')];
+
+        assert!(scan_segment(&segment, signature).is_empty());
+    }
+
+    #[test]
+    fn scan_segment_finds_nothing_for_an_empty_signature() {
+        let haystack = b"AAAA".to_vec();
+        let segment = Segment {
+            start: haystack.as_ptr(),
+            len: haystack.len(),
+        };
+
+        assert!(scan_segment(&segment, &[]).is_empty());
+    }
+}
@@ -25,6 +25,13 @@ pub struct CVar {
     name: &'static [u8],
     /// Storage for the default value.
     default_value: &'static [u8],
+    /// The value observed the last time [`update_change_notifications`] ran, used to detect
+    /// edits made through [`CVar::set_value`]/[`CVar::set_string`] as well as elsewhere (for
+    /// example, through the in-game console).
+    last_value: UnsafeCell<f32>,
+    /// Callback invoked by [`update_change_notifications`] when the value has changed since the
+    /// last check.
+    on_change: UnsafeCell<Option<fn(MainThreadMarker, &'static CVar)>>,
 }
 
 // Safety: all methods accessing `cvar` require a `MainThreadMarker`.
@@ -43,6 +50,8 @@ impl CVar {
             }),
             name,
             default_value,
+            last_value: UnsafeCell::new(0.),
+            on_change: UnsafeCell::new(None),
         }
     }
 
@@ -67,6 +76,80 @@ impl CVar {
 
         raw.value != 0.
     }
+
+    /// Returns the `f32` value of the variable.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the variable is not registered.
+    pub fn as_f32(&self, marker: MainThreadMarker) -> f32 {
+        assert!(self.is_registered(marker));
+
+        // Safety: we're not calling any engine methods while the reference is active.
+        let raw = unsafe { &*self.raw.get() };
+
+        raw.value
+    }
+
+    /// Returns the string value of the variable.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the variable is not registered.
+    pub fn as_string(&self, marker: MainThreadMarker) -> &CStr {
+        assert!(self.is_registered(marker));
+
+        // Safety: we're not calling any engine methods while the reference is active, and the
+        // engine keeps `string` valid for as long as the variable stays registered.
+        let raw = unsafe { &*self.raw.get() };
+
+        unsafe { CStr::from_ptr(raw.string) }
+    }
+
+    /// Sets the variable's value, writing back through the engine's cvar-set path.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the variable is not registered, or if `Cvar_DirectSet` did not resolve.
+    pub fn set_value(&self, marker: MainThreadMarker, value: f32) {
+        assert!(self.is_registered(marker));
+
+        let formatted = format!("{value}\0");
+        let value = CStr::from_bytes_with_nul(formatted.as_bytes()).unwrap();
+        self.set_string(marker, value);
+    }
+
+    /// Sets the variable's string value, writing back through the engine's cvar-set path.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the variable is not registered, or if `Cvar_DirectSet` did not resolve. Reads
+    /// (`as_bool`/`as_f32`/`as_string`) and registration don't depend on that symbol, only this
+    /// write path does, so a build where it failed to resolve still gets a working read-only cvar
+    /// subsystem instead of losing the whole module.
+    pub fn set_string(&self, marker: MainThreadMarker, value: &CStr) {
+        assert!(self.is_registered(marker));
+        assert!(engine::CVAR_DIRECTSET.is_set(marker));
+
+        // Safety: the variable is registered, so `raw` is linked into the engine's cvar list and
+        // `Cvar_DirectSet` is the engine's own path for writing through it.
+        unsafe {
+            engine::CVAR_DIRECTSET.get(marker)(self.raw.get(), value.as_ptr());
+        }
+    }
+
+    /// Registers a callback invoked by [`update_change_notifications`] whenever this variable's
+    /// value changes.
+    pub fn register_on_change(
+        &self,
+        _marker: MainThreadMarker,
+        callback: fn(MainThreadMarker, &'static CVar),
+    ) {
+        // Safety: we're not calling any engine methods while the reference is active.
+        unsafe {
+            *self.on_change.get() = Some(callback);
+        }
+    }
 }
 
 /// Registers the variable in the engine.
@@ -157,6 +240,49 @@ pub unsafe fn register_all_cvars(marker: MainThreadMarker) {
             );
 
             register(marker, cvar);
+
+            // Safety: we're not calling any engine methods while the reference is active. This
+            // establishes the baseline so the first `update_change_notifications()` call after
+            // registration doesn't spuriously fire on the variable's own default value.
+            unsafe {
+                *cvar.last_value.get() = cvar.as_f32(marker);
+            }
+        }
+    }
+}
+
+/// Checks every registered variable for a value change since the last check (either through
+/// [`CVar::set_value`]/[`CVar::set_string`] or the in-game console) and invokes its
+/// [`CVar::register_on_change`] callback, if any.
+///
+/// Should be called once per frame. Nothing in this series calls this yet (no per-frame hook in
+/// this diff drives it) - wiring it into the per-frame hook chain is left for whichever follow-up
+/// adds the first `register_on_change` consumer.
+pub fn update_change_notifications(marker: MainThreadMarker) {
+    if !CVars.is_enabled(marker) {
+        return;
+    }
+
+    for module in MODULES {
+        for cvar in module.cvars() {
+            if !cvar.is_registered(marker) {
+                continue;
+            }
+
+            let value = cvar.as_f32(marker);
+
+            // Safety: we're not calling any engine methods while these references are active.
+            unsafe {
+                let last_value = &mut *cvar.last_value.get();
+                if *last_value == value {
+                    continue;
+                }
+                *last_value = value;
+
+                if let Some(on_change) = *cvar.on_change.get() {
+                    on_change(marker, cvar);
+                }
+            }
         }
     }
 }
@@ -1,4 +1,5 @@
 use std::cmp::min;
+use std::fmt;
 use std::num::NonZeroU32;
 
 use hltas::types::{FrameBulk, Line};
@@ -55,6 +56,12 @@ pub enum Operation {
         from: u32,
         to: u32,
     },
+    /// Several operations applied and undone together as a single logical edit.
+    Sequence(Vec<Operation>),
+    MoveLine {
+        from_idx: usize,
+        to_idx: usize,
+    },
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -74,52 +81,125 @@ pub enum Key {
     Reload,
 }
 
+/// An error returned by [`Operation::apply`] or [`Operation::undo`].
+///
+/// These operations are deserialized from a persistent (SQLite/bincode) store and can therefore
+/// be stale or corrupted (e.g. written by an older, incompatible version of the editor). Rather
+/// than panicking and taking down the whole process, invalid input is reported through this type
+/// so the caller can skip or quarantine the offending operation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OperationError {
+    /// A `bulk_idx` does not refer to an existing frame bulk.
+    BulkIndexOutOfRange { bulk_idx: usize },
+    /// A `line_idx` does not refer to an existing line.
+    LineIndexOutOfRange { line_idx: usize },
+    /// A `frame_idx` does not refer to an existing frame.
+    FrameIndexOutOfRange { frame_idx: usize },
+    /// The HLTAS did not contain the value the operation expected to find before modifying it.
+    StateMismatch { expected: String, found: String },
+    /// A stored HLTAS line could not be parsed.
+    ParseError { line: String },
+}
+
+impl fmt::Display for OperationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BulkIndexOutOfRange { bulk_idx } => {
+                write!(f, "bulk index {bulk_idx} is out of range")
+            }
+            Self::LineIndexOutOfRange { line_idx } => {
+                write!(f, "line index {line_idx} is out of range")
+            }
+            Self::FrameIndexOutOfRange { frame_idx } => {
+                write!(f, "frame index {frame_idx} is out of range")
+            }
+            Self::StateMismatch { expected, found } => {
+                write!(f, "expected {expected}, found {found}")
+            }
+            Self::ParseError { line } => write!(f, "could not parse line: {line}"),
+        }
+    }
+}
+
+impl std::error::Error for OperationError {}
+
+/// Returns the smaller of two optional first-affected-frame indices, treating `None` as "no
+/// effect" rather than as smaller than every `Some`.
+fn min_first_frame_idx(a: Option<usize>, b: Option<usize>) -> Option<usize> {
+    match (a, b) {
+        (None, None) => None,
+        (Some(x), None) | (None, Some(x)) => Some(x),
+        (Some(x), Some(y)) => Some(min(x, y)),
+    }
+}
+
 // The semantics of apply() or undo() MUST NOT CHANGE, because that will break persistent undo/redo
 // for old projects.
 impl Operation {
     /// Applies operation to HLTAS and returns index of first affected frame.
     ///
     /// Returns `None` if all frames remain valid.
-    pub fn apply(&self, hltas: &mut HLTAS) -> Option<usize> {
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the operation does not match the current state of `hltas` (for
+    /// example, a stale or corrupted index, or a line that no longer parses). `hltas` is left
+    /// unmodified in that case.
+    pub fn apply(&self, hltas: &mut HLTAS) -> Result<Option<usize>, OperationError> {
         match *self {
             Operation::SetFrameCount { bulk_idx, from, to } => {
                 let (bulk, first_frame_idx) = bulk_and_first_frame_idx(hltas)
                     .nth(bulk_idx)
-                    .expect("invalid bulk index");
+                    .ok_or(OperationError::BulkIndexOutOfRange { bulk_idx })?;
 
-                assert_eq!(bulk.frame_count.get(), from, "wrong current frame count");
+                if bulk.frame_count.get() != from {
+                    return Err(OperationError::StateMismatch {
+                        expected: from.to_string(),
+                        found: bulk.frame_count.get().to_string(),
+                    });
+                }
 
                 if from != to {
                     bulk.frame_count = NonZeroU32::new(to).expect("invalid new frame count");
-                    return Some(first_frame_idx + min(from, to) as usize);
+                    return Ok(Some(first_frame_idx + min(from, to) as usize));
                 }
             }
             Operation::SetYaw { bulk_idx, from, to } => {
                 let (bulk, first_frame_idx) = bulk_and_first_frame_idx(hltas)
                     .nth(bulk_idx)
-                    .expect("invalid bulk index");
+                    .ok_or(OperationError::BulkIndexOutOfRange { bulk_idx })?;
 
                 let yaw = bulk.yaw_mut().expect("frame bulk should have yaw");
-                assert_eq!(*yaw, from, "wrong current yaw");
+                if *yaw != from {
+                    return Err(OperationError::StateMismatch {
+                        expected: from.to_string(),
+                        found: yaw.to_string(),
+                    });
+                }
 
                 if *yaw != to {
                     *yaw = to;
-                    return Some(first_frame_idx);
+                    return Ok(Some(first_frame_idx));
                 }
             }
             Operation::Delete { line_idx, .. } => {
                 let first_frame_idx = line_first_frame_idx(hltas)
                     .nth(line_idx)
-                    .expect("invalid line index");
+                    .ok_or(OperationError::LineIndexOutOfRange { line_idx })?;
 
                 hltas.lines.remove(line_idx);
-                return Some(first_frame_idx);
+                return Ok(Some(first_frame_idx));
             }
             Operation::Split { frame_idx } => {
                 let (line_idx, repeat) = line_idx_and_repeat_at_frame(&hltas.lines, frame_idx)
-                    .expect("invalid frame index");
+                    .ok_or(OperationError::FrameIndexOutOfRange { frame_idx })?;
 
-                assert!(repeat > 0, "repeat should be above 0");
+                if repeat == 0 {
+                    return Err(OperationError::StateMismatch {
+                        expected: "repeat above 0".to_string(),
+                        found: repeat.to_string(),
+                    });
+                }
 
                 let bulk = hltas.lines[line_idx].frame_bulk_mut().unwrap();
                 let mut new_bulk = bulk.clone();
@@ -134,111 +214,201 @@ impl Operation {
             Operation::Replace {
                 line_idx, ref to, ..
             } => {
-                let to = hltas::read::line(to).expect("line should be parse-able").1;
+                let to = hltas::read::line(to)
+                    .map_err(|_| OperationError::ParseError { line: to.clone() })?
+                    .1;
 
                 let first_frame_idx = line_first_frame_idx(hltas)
                     .nth(line_idx)
-                    .expect("invalid line index");
+                    .ok_or(OperationError::LineIndexOutOfRange { line_idx })?;
 
                 hltas.lines[line_idx] = to;
-                return Some(first_frame_idx);
+                return Ok(Some(first_frame_idx));
             }
             Operation::ToggleKey { bulk_idx, key, to } => {
                 let (bulk, first_frame_idx) = bulk_and_first_frame_idx(hltas)
                     .nth(bulk_idx)
-                    .expect("invalid bulk index");
+                    .ok_or(OperationError::BulkIndexOutOfRange { bulk_idx })?;
 
                 let value = key.value_mut(bulk);
-                assert_ne!(*value, to);
+                if *value == to {
+                    return Err(OperationError::StateMismatch {
+                        expected: (!to).to_string(),
+                        found: value.to_string(),
+                    });
+                }
                 *value = to;
-                return Some(first_frame_idx);
+                return Ok(Some(first_frame_idx));
             }
             Operation::Insert { line_idx, ref line } => {
+                if line_idx > hltas.lines.len() {
+                    return Err(OperationError::LineIndexOutOfRange { line_idx });
+                }
+
                 let line = hltas::read::line(line)
-                    .expect("line should be parse-able")
+                    .map_err(|_| OperationError::ParseError { line: line.clone() })?
                     .1;
 
                 hltas.lines.insert(line_idx, line);
 
                 let first_frame_idx = line_first_frame_idx(hltas)
                     .nth(line_idx)
-                    .expect("invalid line index");
+                    .expect("line_idx was validated above and the vector just grew by one");
 
-                return Some(first_frame_idx);
+                return Ok(Some(first_frame_idx));
             }
             Operation::SetLeftRightCount { bulk_idx, from, to } => {
                 let (bulk, first_frame_idx) = bulk_and_first_frame_idx(hltas)
                     .nth(bulk_idx)
-                    .expect("invalid bulk index");
+                    .ok_or(OperationError::BulkIndexOutOfRange { bulk_idx })?;
 
                 let count = bulk
                     .left_right_count_mut()
                     .expect("frame bulk should have left-right count");
-                assert_eq!(count.get(), from, "wrong current left-right count");
+                if count.get() != from {
+                    return Err(OperationError::StateMismatch {
+                        expected: from.to_string(),
+                        found: count.get().to_string(),
+                    });
+                }
 
                 if from != to {
                     *count = NonZeroU32::new(to).expect("invalid new left-right count");
-                    return Some(first_frame_idx);
+                    return Ok(Some(first_frame_idx));
+                }
+            }
+            Operation::Sequence(ref ops) => {
+                let mut first_frame_idx = None;
+
+                for (idx, op) in ops.iter().enumerate() {
+                    match op.apply(hltas) {
+                        Ok(affected) => {
+                            first_frame_idx = min_first_frame_idx(first_frame_idx, affected)
+                        }
+                        Err(err) => {
+                            // Roll back the already-applied prefix so the HLTAS is never left
+                            // half-modified.
+                            for op in ops[..idx].iter().rev() {
+                                op.undo(hltas).expect(
+                                    "undoing a just-applied operation should not fail",
+                                );
+                            }
+
+                            return Err(err);
+                        }
+                    }
                 }
+
+                return Ok(first_frame_idx);
+            }
+            Operation::MoveLine { from_idx, to_idx } => {
+                let from_first_frame_idx = line_first_frame_idx(hltas)
+                    .nth(from_idx)
+                    .ok_or(OperationError::LineIndexOutOfRange { line_idx: from_idx })?;
+                let to_first_frame_idx = line_first_frame_idx(hltas)
+                    .nth(to_idx)
+                    .ok_or(OperationError::LineIndexOutOfRange { line_idx: to_idx })?;
+
+                let line = hltas.lines.remove(from_idx);
+                hltas.lines.insert(to_idx, line);
+
+                return Ok(Some(min(from_first_frame_idx, to_first_frame_idx)));
             }
         }
 
-        None
+        Ok(None)
     }
 
     /// Undoes operation on HLTAS and returns index of first affected frame.
     ///
     /// Returns `None` if all frames remain valid.
-    pub fn undo(&self, hltas: &mut HLTAS) -> Option<usize> {
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the operation does not match the current state of `hltas`. `hltas` is
+    /// left unmodified in that case.
+    pub fn undo(&self, hltas: &mut HLTAS) -> Result<Option<usize>, OperationError> {
         match *self {
             Operation::SetFrameCount { bulk_idx, from, to } => {
                 let (bulk, first_frame_idx) = bulk_and_first_frame_idx(hltas)
                     .nth(bulk_idx)
-                    .expect("invalid bulk index");
+                    .ok_or(OperationError::BulkIndexOutOfRange { bulk_idx })?;
 
-                assert_eq!(bulk.frame_count.get(), to, "wrong current frame count");
+                if bulk.frame_count.get() != to {
+                    return Err(OperationError::StateMismatch {
+                        expected: to.to_string(),
+                        found: bulk.frame_count.get().to_string(),
+                    });
+                }
 
                 if from != to {
                     bulk.frame_count = NonZeroU32::new(from).expect("invalid original frame count");
-                    return Some(first_frame_idx + min(from, to) as usize);
+                    return Ok(Some(first_frame_idx + min(from, to) as usize));
                 }
             }
             Operation::SetYaw { bulk_idx, from, to } => {
                 let (bulk, first_frame_idx) = bulk_and_first_frame_idx(hltas)
                     .nth(bulk_idx)
-                    .expect("invalid bulk index");
+                    .ok_or(OperationError::BulkIndexOutOfRange { bulk_idx })?;
 
                 let yaw = bulk.yaw_mut().expect("frame bulk should have yaw");
-                assert_eq!(*yaw, to, "wrong current yaw");
+                if *yaw != to {
+                    return Err(OperationError::StateMismatch {
+                        expected: to.to_string(),
+                        found: yaw.to_string(),
+                    });
+                }
 
                 if *yaw != from {
                     *yaw = from;
-                    return Some(first_frame_idx);
+                    return Ok(Some(first_frame_idx));
                 }
             }
             Operation::Delete { line_idx, ref line } => {
+                if line_idx > hltas.lines.len() {
+                    return Err(OperationError::LineIndexOutOfRange { line_idx });
+                }
+
                 let line = hltas::read::line(line)
-                    .expect("line should be parse-able")
+                    .map_err(|_| OperationError::ParseError { line: line.clone() })?
                     .1;
 
                 hltas.lines.insert(line_idx, line);
 
                 let first_frame_idx = line_first_frame_idx(hltas)
                     .nth(line_idx)
-                    .expect("invalid line index");
+                    .expect("line_idx was validated above and the vector just grew by one");
 
-                return Some(first_frame_idx);
+                return Ok(Some(first_frame_idx));
             }
             Operation::Split { frame_idx } => {
                 let (line_idx, repeat) = line_idx_and_repeat_at_frame(&hltas.lines, frame_idx)
-                    .expect("invalid frame index");
+                    .ok_or(OperationError::FrameIndexOutOfRange { frame_idx })?;
 
-                assert_eq!(repeat, 0, "current repeat should be 0");
-                assert!(line_idx > 0, "line index should be above 0");
+                if repeat != 0 {
+                    return Err(OperationError::StateMismatch {
+                        expected: "0".to_string(),
+                        found: repeat.to_string(),
+                    });
+                }
+                if line_idx == 0 {
+                    return Err(OperationError::LineIndexOutOfRange { line_idx });
+                }
+                if !matches!(hltas.lines[line_idx - 1], Line::FrameBulk(_)) {
+                    return Err(OperationError::StateMismatch {
+                        expected: "frame bulk".to_string(),
+                        found: "other line".to_string(),
+                    });
+                }
 
                 let prev_bulk = match hltas.lines.remove(line_idx - 1) {
                     Line::FrameBulk(prev_bulk) => prev_bulk,
-                    _ => panic!("previous line should be frame bulk"),
+                    _ => {
+                        return Err(OperationError::StateMismatch {
+                            expected: "frame bulk".to_string(),
+                            found: "other line".to_string(),
+                        })
+                    }
                 };
                 let bulk = hltas.lines[line_idx - 1].frame_bulk_mut().unwrap();
                 bulk.frame_count = bulk
@@ -252,52 +422,154 @@ impl Operation {
                 line_idx, ref from, ..
             } => {
                 let from = hltas::read::line(from)
-                    .expect("line should be parse-able")
+                    .map_err(|_| OperationError::ParseError { line: from.clone() })?
                     .1;
 
                 let first_frame_idx = line_first_frame_idx(hltas)
                     .nth(line_idx)
-                    .expect("invalid line index");
+                    .ok_or(OperationError::LineIndexOutOfRange { line_idx })?;
 
                 hltas.lines[line_idx] = from;
-                return Some(first_frame_idx);
+                return Ok(Some(first_frame_idx));
             }
             Operation::ToggleKey { bulk_idx, key, to } => {
                 let (bulk, first_frame_idx) = bulk_and_first_frame_idx(hltas)
                     .nth(bulk_idx)
-                    .expect("invalid bulk index");
+                    .ok_or(OperationError::BulkIndexOutOfRange { bulk_idx })?;
 
                 let value = key.value_mut(bulk);
-                assert_eq!(*value, to);
+                if *value != to {
+                    return Err(OperationError::StateMismatch {
+                        expected: to.to_string(),
+                        found: value.to_string(),
+                    });
+                }
                 *value = !to;
-                return Some(first_frame_idx);
+                return Ok(Some(first_frame_idx));
             }
             Operation::Insert { line_idx, .. } => {
                 let first_frame_idx = line_first_frame_idx(hltas)
                     .nth(line_idx)
-                    .expect("invalid line index");
+                    .ok_or(OperationError::LineIndexOutOfRange { line_idx })?;
 
                 hltas.lines.remove(line_idx);
-                return Some(first_frame_idx);
+                return Ok(Some(first_frame_idx));
             }
             Operation::SetLeftRightCount { bulk_idx, from, to } => {
                 let (bulk, first_frame_idx) = bulk_and_first_frame_idx(hltas)
                     .nth(bulk_idx)
-                    .expect("invalid bulk index");
+                    .ok_or(OperationError::BulkIndexOutOfRange { bulk_idx })?;
 
                 let count = bulk
                     .left_right_count_mut()
                     .expect("frame bulk should have left-right count");
-                assert_eq!(count.get(), to, "wrong current left-right count");
+                if count.get() != to {
+                    return Err(OperationError::StateMismatch {
+                        expected: to.to_string(),
+                        found: count.get().to_string(),
+                    });
+                }
 
                 if from != to {
                     *count = NonZeroU32::new(from).expect("invalid original left-right count");
-                    return Some(first_frame_idx);
+                    return Ok(Some(first_frame_idx));
+                }
+            }
+            Operation::Sequence(ref ops) => {
+                let mut first_frame_idx = None;
+
+                for (idx, op) in ops.iter().enumerate().rev() {
+                    match op.undo(hltas) {
+                        Ok(affected) => {
+                            first_frame_idx = min_first_frame_idx(first_frame_idx, affected)
+                        }
+                        Err(err) => {
+                            // Re-apply the already-undone suffix so the HLTAS is never left
+                            // half-modified.
+                            for op in &ops[idx + 1..] {
+                                op.apply(hltas)
+                                    .expect("re-applying a just-undone operation should not fail");
+                            }
+
+                            return Err(err);
+                        }
+                    }
                 }
+
+                return Ok(first_frame_idx);
+            }
+            Operation::MoveLine { from_idx, to_idx } => {
+                let from_first_frame_idx = line_first_frame_idx(hltas)
+                    .nth(from_idx)
+                    .ok_or(OperationError::LineIndexOutOfRange { line_idx: from_idx })?;
+                let to_first_frame_idx = line_first_frame_idx(hltas)
+                    .nth(to_idx)
+                    .ok_or(OperationError::LineIndexOutOfRange { line_idx: to_idx })?;
+
+                let line = hltas.lines.remove(to_idx);
+                hltas.lines.insert(from_idx, line);
+
+                return Ok(Some(min(from_first_frame_idx, to_first_frame_idx)));
             }
         }
 
-        None
+        Ok(None)
+    }
+}
+
+impl Operation {
+    /// Returns `true` if `self` and `other` are continuous-value edits of the same frame bulk,
+    /// meaning `other` can be folded into `self` with [`coalesce`](Self::coalesce) instead of
+    /// pushing a new undo entry.
+    ///
+    /// Structural operations ([`Insert`](Operation::Insert), [`Delete`](Operation::Delete),
+    /// [`Split`](Operation::Split), [`Replace`](Operation::Replace)) are never coalesced: the
+    /// caller is expected to break the coalescing chain on those, and on selection/bulk changes
+    /// or an explicit commit boundary.
+    ///
+    /// Nothing outside this module's tests calls this or [`coalesce`](Self::coalesce), so
+    /// operations recorded in the persistent undo/redo history are not coalesced.
+    pub fn can_coalesce(&self, other: &Operation) -> bool {
+        match (self, other) {
+            (
+                Operation::SetFrameCount { bulk_idx: a, .. },
+                Operation::SetFrameCount { bulk_idx: b, .. },
+            )
+            | (Operation::SetYaw { bulk_idx: a, .. }, Operation::SetYaw { bulk_idx: b, .. })
+            | (
+                Operation::SetLeftRightCount { bulk_idx: a, .. },
+                Operation::SetLeftRightCount { bulk_idx: b, .. },
+            ) => a == b,
+            _ => false,
+        }
+    }
+
+    /// Folds `other` into `self`, keeping `self`'s original `from` and adopting `other`'s `to`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self.can_coalesce(&other)` is `false`.
+    pub fn coalesce(&mut self, other: Operation) {
+        assert!(
+            self.can_coalesce(&other),
+            "coalesce() called on non-coalescable operations"
+        );
+
+        match (self, other) {
+            (Operation::SetFrameCount { to, .. }, Operation::SetFrameCount { to: new_to, .. }) => {
+                *to = new_to;
+            }
+            (Operation::SetYaw { to, .. }, Operation::SetYaw { to: new_to, .. }) => {
+                *to = new_to;
+            }
+            (
+                Operation::SetLeftRightCount { to, .. },
+                Operation::SetLeftRightCount { to: new_to, .. },
+            ) => {
+                *to = new_to;
+            }
+            _ => unreachable!("can_coalesce() already guarantees matching variants"),
+        }
     }
 }
 
@@ -333,14 +605,14 @@ mod tests {
 
         let mut modified = input.clone();
         assert_ne!(
-            op.apply(&mut modified),
+            op.apply(&mut modified).unwrap(),
             Some(0),
             "initial frame should never be invalidated"
         );
         assert_eq!(modified, output, "apply produced wrong result");
 
         assert_ne!(
-            op.undo(&mut modified),
+            op.undo(&mut modified).unwrap(),
             Some(0),
             "initial frame should never be invalidated"
         );
@@ -471,4 +743,205 @@ mod tests {
         check_key("------|----2-", Key::Attack2);
         check_key("------|-----r", Key::Reload);
     }
+
+    #[test]
+    fn op_move_line() {
+        check_op(
+            "----------|------|------|0.004|10|-|4\n\
+            ----------|------|------|0.004|20|-|2",
+            Operation::MoveLine {
+                from_idx: 1,
+                to_idx: 0,
+            },
+            "----------|------|------|0.004|20|-|2\n\
+            ----------|------|------|0.004|10|-|4",
+        );
+    }
+
+    #[test]
+    fn op_sequence() {
+        check_op(
+            "----------|------|------|0.004|10|-|6",
+            Operation::Sequence(vec![
+                Operation::Split { frame_idx: 4 },
+                Operation::ToggleKey {
+                    bulk_idx: 0,
+                    key: Key::Jump,
+                    to: true,
+                },
+                Operation::ToggleKey {
+                    bulk_idx: 1,
+                    key: Key::Jump,
+                    to: true,
+                },
+            ]),
+            "----------|------|j-----|0.004|10|-|4\n\
+            ----------|------|j-----|0.004|10|-|2",
+        );
+    }
+
+    #[test]
+    fn sequence_rolls_back_on_failure() {
+        let hltas = HLTAS::from_str("version 1\nframes\n----------|------|------|0.004|10|-|6")
+            .unwrap();
+        let mut modified = hltas.clone();
+
+        let err = Operation::Sequence(vec![
+            Operation::SetYaw {
+                bulk_idx: 0,
+                from: 10.,
+                to: 15.,
+            },
+            Operation::SetYaw {
+                bulk_idx: 0,
+                from: 999., // stale `from`: this op will fail to apply
+                to: 20.,
+            },
+        ])
+        .apply(&mut modified)
+        .unwrap_err();
+
+        assert_eq!(
+            err,
+            OperationError::StateMismatch {
+                expected: "999".to_string(),
+                found: "15".to_string(),
+            }
+        );
+        assert_eq!(
+            modified, hltas,
+            "a failed sequence must roll back the already-applied prefix"
+        );
+    }
+
+    #[test]
+    fn coalesce_same_variant_same_bulk() {
+        let mut op = Operation::SetYaw {
+            bulk_idx: 0,
+            from: 10.,
+            to: 15.,
+        };
+        let other = Operation::SetYaw {
+            bulk_idx: 0,
+            from: 15.,
+            to: 20.,
+        };
+
+        assert!(op.can_coalesce(&other));
+        op.coalesce(other);
+
+        assert_eq!(
+            op,
+            Operation::SetYaw {
+                bulk_idx: 0,
+                from: 10.,
+                to: 20.,
+            },
+            "coalescing should keep the original `from` and adopt the new `to`"
+        );
+    }
+
+    #[test]
+    fn cannot_coalesce_different_bulk_or_variant() {
+        let yaw = Operation::SetYaw {
+            bulk_idx: 0,
+            from: 10.,
+            to: 15.,
+        };
+
+        assert!(!yaw.can_coalesce(&Operation::SetYaw {
+            bulk_idx: 1,
+            from: 10.,
+            to: 15.,
+        }));
+        assert!(!yaw.can_coalesce(&Operation::SetFrameCount {
+            bulk_idx: 0,
+            from: 10,
+            to: 15,
+        }));
+        assert!(!Operation::Insert {
+            line_idx: 0,
+            line: "----------|------|------|0.004|10|-|4".to_string(),
+        }
+        .can_coalesce(&Operation::Insert {
+            line_idx: 0,
+            line: "----------|------|------|0.004|10|-|4".to_string(),
+        }));
+    }
+
+    #[test]
+    fn insert_apply_reports_out_of_range_line_idx_instead_of_panicking() {
+        let hltas = HLTAS::from_str("version 1\nframes\n----------|------|------|0.004|10|-|6")
+            .unwrap();
+        let mut modified = hltas.clone();
+
+        let err = Operation::Insert {
+            line_idx: 5,
+            line: "----------|------|------|0.004|10|-|4".to_string(),
+        }
+        .apply(&mut modified)
+        .unwrap_err();
+
+        assert_eq!(err, OperationError::LineIndexOutOfRange { line_idx: 5 });
+        assert_eq!(modified, hltas, "a failed apply must not modify the HLTAS");
+    }
+
+    #[test]
+    fn delete_undo_reports_out_of_range_line_idx_instead_of_panicking() {
+        let hltas = HLTAS::from_str("version 1\nframes\n----------|------|------|0.004|10|-|6")
+            .unwrap();
+        let mut modified = hltas.clone();
+
+        let err = Operation::Delete {
+            line_idx: 5,
+            line: "----------|------|------|0.004|10|-|4".to_string(),
+        }
+        .undo(&mut modified)
+        .unwrap_err();
+
+        assert_eq!(err, OperationError::LineIndexOutOfRange { line_idx: 5 });
+        assert_eq!(modified, hltas, "a failed undo must not modify the HLTAS");
+    }
+
+    #[test]
+    fn apply_reports_out_of_range_bulk_idx() {
+        let hltas = HLTAS::from_str("version 1\nframes\n----------|------|------|0.004|10|-|6")
+            .unwrap();
+        let mut modified = hltas.clone();
+
+        let err = Operation::SetYaw {
+            bulk_idx: 5,
+            from: 10.,
+            to: 15.,
+        }
+        .apply(&mut modified)
+        .unwrap_err();
+
+        assert_eq!(err, OperationError::BulkIndexOutOfRange { bulk_idx: 5 });
+        assert_eq!(modified, hltas, "a failed apply must not modify the HLTAS");
+    }
+
+    #[test]
+    fn apply_reports_state_mismatch() {
+        let hltas = HLTAS::from_str("version 1\nframes\n----------|------|------|0.004|10|-|6")
+            .unwrap();
+        let mut modified = hltas.clone();
+
+        let err = Operation::SetYaw {
+            bulk_idx: 0,
+            from: 20.,
+            to: 15.,
+        }
+        .apply(&mut modified)
+        .unwrap_err();
+
+        assert_eq!(
+            err,
+            OperationError::StateMismatch {
+                expected: "20".to_string(),
+                found: "10".to_string(),
+            }
+        );
+        assert_eq!(modified, hltas, "a failed apply must not modify the HLTAS");
+    }
 }